@@ -0,0 +1,28 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use xxhash_rust::xxh3::Xxh3;
+
+/// Content-addressed cache key over a source file's bytes plus the full
+/// parameter set used to process it (resize mode, bayer order, dither
+/// method, color mode, ...). Re-running with the same inputs produces the
+/// same key, so already-processed files can be skipped.
+pub fn content_key(file_bytes: &[u8], params: &str) -> String {
+    let mut hasher = Xxh3::new();
+    hasher.update(file_bytes);
+    hasher.update(params.as_bytes());
+    format!("{:016x}", hasher.digest())
+}
+
+/// Where a processed image with cache key `key` would be written: alongside
+/// the original file, under a `dithers/` subfolder, named by the key and
+/// using `ext` (no leading dot) as the extension.
+pub fn output_path(original_path: &Path, key: &str, ext: &str) -> anyhow::Result<PathBuf> {
+    let dither_dir = original_path
+        .parent()
+        .unwrap_or_else(|| Path::new(""))
+        .join("dithers");
+
+    fs::create_dir_all(&dither_dir)?;
+
+    Ok(dither_dir.join(format!("{key}.{ext}")))
+}
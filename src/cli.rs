@@ -0,0 +1,103 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use std::path::PathBuf;
+
+/// Batch-dither images with ordered or error-diffusion algorithms.
+#[derive(Debug, Parser)]
+#[command(name = "dither_rs", version, about)]
+pub struct Cli {
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Dither one image or a whole directory
+    Dither(DitherArgs),
+    /// Report counts, sizes and dimensions for a directory without processing it
+    Stats(StatsArgs),
+}
+
+/// Fixed max-side presets, as a shorthand for `--max-side`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum SizeOption {
+    Small,
+    Medium,
+    Large,
+}
+
+impl SizeOption {
+    pub fn max_side(self) -> u32 {
+        match self {
+            SizeOption::Small => 400,
+            SizeOption::Medium => 800,
+            SizeOption::Large => 1600,
+        }
+    }
+}
+
+/// Whether to process a single file or recurse through a directory.
+#[derive(Debug, Clone, Copy, ValueEnum, PartialEq, Eq)]
+pub enum Mode {
+    All,
+    Single,
+}
+
+#[derive(Debug, Args)]
+pub struct DitherArgs {
+    /// Directory to recurse (mode=all) or a single image file (mode=single)
+    pub path: PathBuf,
+
+    /// Whether to process a single file or recurse a directory
+    #[arg(long, value_enum, default_value = "all")]
+    pub mode: Mode,
+
+    /// Size preset, as a shorthand for --max-side
+    #[arg(long, value_enum)]
+    pub size: Option<SizeOption>,
+
+    /// Maximum side length in pixels (ignored if --size or --resize is set)
+    #[arg(long, default_value_t = 800)]
+    pub max_side: u32,
+
+    /// Explicit resize mode, e.g. scale=200x100, fit-width=200, fit=200x100, fill=200x100
+    #[arg(long)]
+    pub resize: Option<String>,
+
+    /// Order of the Bayer matrix used for ordered dithering (must be a power of 2)
+    #[arg(long, default_value_t = 8)]
+    pub bayer_order: usize,
+
+    /// Dithering algorithm: bayer, floyd-steinberg, atkinson, jarvis
+    #[arg(long, default_value = "bayer")]
+    pub dither_method: String,
+
+    /// Generate an N-color palette (power of 2) instead of 1-bit black/white
+    #[arg(long)]
+    pub colors: Option<u32>,
+
+    /// Dither against a fixed comma-separated hex palette instead of --colors
+    #[arg(long)]
+    pub palette: Option<String>,
+
+    /// Reprocess even if a cached output already exists
+    #[arg(long)]
+    pub force: bool,
+
+    /// Output format: png, jpeg[:quality], webp, bmp, gif
+    #[arg(long)]
+    pub format: Option<String>,
+
+    /// Save using the input file's own extension instead of --format's
+    #[arg(long)]
+    pub keep_format: bool,
+
+    /// Number of worker threads (default: rayon's automatic choice)
+    #[arg(long)]
+    pub threads: Option<usize>,
+}
+
+#[derive(Debug, Args)]
+pub struct StatsArgs {
+    /// Directory to report on
+    pub path: PathBuf,
+}
@@ -0,0 +1,69 @@
+use anyhow::{Context, Result};
+use image::DynamicImage;
+use std::path::Path;
+
+#[cfg(feature = "heif")]
+use crate::files::HEIF_EXTENSIONS;
+#[cfg(feature = "raw")]
+use crate::files::RAW_EXTENSIONS;
+
+/// Decode an image file into a [`DynamicImage`], dispatching to the decoder
+/// that matches its extension. HEIC/HEIF/AVIF and camera-RAW files need the
+/// `heif`/`raw` features; without them their extensions aren't even listed
+/// by [`crate::files::list_image_files`], so this only has to handle the
+/// case where a caller points directly at one.
+pub fn open(bytes: &[u8], path: &Path) -> Result<DynamicImage> {
+    #[cfg(any(feature = "heif", feature = "raw"))]
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .unwrap_or("")
+        .to_lowercase();
+
+    #[cfg(feature = "heif")]
+    if HEIF_EXTENSIONS.contains(&ext.as_str()) {
+        return open_heif(path);
+    }
+
+    #[cfg(feature = "raw")]
+    if RAW_EXTENSIONS.contains(&ext.as_str()) {
+        return open_raw(path);
+    }
+
+    image::load_from_memory(bytes)
+        .with_context(|| format!("Failed to open image: {}", path.display()))
+}
+
+#[cfg(feature = "heif")]
+fn open_heif(path: &Path) -> Result<DynamicImage> {
+    use image::ImageBuffer;
+    use libheif_rs::{ColorSpace, HeifContext, RgbChroma};
+
+    let path_str = path.to_str().context("Non-UTF8 path")?;
+    let ctx = HeifContext::read_from_file(path_str)
+        .with_context(|| format!("Failed to open HEIF image: {}", path.display()))?;
+    let handle = ctx
+        .primary_image_handle()
+        .with_context(|| format!("No primary image in: {}", path.display()))?;
+    let image = handle.decode(ColorSpace::Rgb(RgbChroma::Rgb), false)?;
+    let plane = image
+        .planes()
+        .interleaved
+        .context("HEIF image is missing its interleaved RGB plane")?;
+
+    ImageBuffer::from_raw(image.width(), image.height(), plane.data.to_vec())
+        .map(DynamicImage::ImageRgb8)
+        .context("Failed to build an image buffer from HEIF data")
+}
+
+#[cfg(feature = "raw")]
+fn open_raw(path: &Path) -> Result<DynamicImage> {
+    use image::ImageBuffer;
+
+    let developed = imagepipe::simple_decode_8bit(path, 0, 0)
+        .map_err(|e| anyhow::anyhow!("Failed to develop RAW image {}: {e}", path.display()))?;
+
+    ImageBuffer::from_raw(developed.width as u32, developed.height as u32, developed.data)
+        .map(DynamicImage::ImageRgb8)
+        .context("Failed to build an image buffer from RAW data")
+}
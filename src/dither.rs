@@ -0,0 +1,296 @@
+use crate::palette;
+use image::{ImageBuffer, Luma, Rgb};
+
+/// Selectable dithering algorithm.
+///
+/// `Bayer` is ordered thresholding against a fixed matrix; the others are
+/// error-diffusion methods that push the quantization error of each pixel
+/// onto its not-yet-processed neighbors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DitherMethod {
+    Bayer,
+    FloydSteinberg,
+    Atkinson,
+    Jarvis,
+}
+
+impl DitherMethod {
+    pub fn name(&self) -> &'static str {
+        match self {
+            DitherMethod::Bayer => "bayer",
+            DitherMethod::FloydSteinberg => "floyd-steinberg",
+            DitherMethod::Atkinson => "atkinson",
+            DitherMethod::Jarvis => "jarvis",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "bayer" => Some(DitherMethod::Bayer),
+            "floyd-steinberg" | "floyd" | "fs" => Some(DitherMethod::FloydSteinberg),
+            "atkinson" => Some(DitherMethod::Atkinson),
+            "jarvis" => Some(DitherMethod::Jarvis),
+            _ => None,
+        }
+    }
+}
+
+/// One error-diffusion tap: offset from the current pixel plus the fraction
+/// of the quantization error to push there.
+struct ErrorTap {
+    dx: i32,
+    dy: i32,
+    weight: f32,
+}
+
+const fn tap(dx: i32, dy: i32, weight: f32) -> ErrorTap {
+    ErrorTap { dx, dy, weight }
+}
+
+const FLOYD_STEINBERG: &[ErrorTap] = &[
+    tap(1, 0, 7.0 / 16.0),
+    tap(-1, 1, 3.0 / 16.0),
+    tap(0, 1, 5.0 / 16.0),
+    tap(1, 1, 1.0 / 16.0),
+];
+
+const ATKINSON: &[ErrorTap] = &[
+    tap(1, 0, 1.0 / 8.0),
+    tap(2, 0, 1.0 / 8.0),
+    tap(-1, 1, 1.0 / 8.0),
+    tap(0, 1, 1.0 / 8.0),
+    tap(1, 1, 1.0 / 8.0),
+    tap(0, 2, 1.0 / 8.0),
+];
+
+const JARVIS: &[ErrorTap] = &[
+    tap(1, 0, 7.0 / 48.0),
+    tap(2, 0, 5.0 / 48.0),
+    tap(-2, 1, 3.0 / 48.0),
+    tap(-1, 1, 5.0 / 48.0),
+    tap(0, 1, 7.0 / 48.0),
+    tap(1, 1, 5.0 / 48.0),
+    tap(2, 1, 3.0 / 48.0),
+    tap(-2, 2, 1.0 / 48.0),
+    tap(-1, 2, 3.0 / 48.0),
+    tap(0, 2, 5.0 / 48.0),
+    tap(1, 2, 3.0 / 48.0),
+    tap(2, 2, 1.0 / 48.0),
+];
+
+/// Dither a grayscale buffer (one `f32` sample per pixel, raster order) to
+/// pure black/white using `method`.
+///
+/// `bayer_matrix`/`bayer_order`/`bayer_scale_factor` are only consulted for
+/// [`DitherMethod::Bayer`]; the error-diffusion methods ignore them.
+pub fn dither(
+    width: u32,
+    height: u32,
+    gray: Vec<f32>,
+    method: DitherMethod,
+    bayer_matrix: &[Vec<u32>],
+    bayer_order: usize,
+    bayer_scale_factor: u32,
+) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+    match method {
+        DitherMethod::Bayer => {
+            dither_bayer(width, height, &gray, bayer_matrix, bayer_order, bayer_scale_factor)
+        }
+        DitherMethod::FloydSteinberg => dither_error_diffusion(width, height, gray, FLOYD_STEINBERG),
+        DitherMethod::Atkinson => dither_error_diffusion(width, height, gray, ATKINSON),
+        DitherMethod::Jarvis => dither_error_diffusion(width, height, gray, JARVIS),
+    }
+}
+
+/// Dither an RGB buffer (one `[f32; 3]` sample per pixel, raster order)
+/// against `palette` using `method`. Bayer thresholding perturbs each
+/// channel by the matrix before picking the nearest palette color; the
+/// error-diffusion methods diffuse each channel's quantization error
+/// independently, the same way [`dither`] does for grayscale.
+pub fn dither_color(
+    width: u32,
+    height: u32,
+    rgb: Vec<[f32; 3]>,
+    palette: &[[u8; 3]],
+    method: DitherMethod,
+    bayer_matrix: &[Vec<u32>],
+    bayer_order: usize,
+) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    match method {
+        DitherMethod::Bayer => {
+            dither_color_bayer(width, height, &rgb, palette, bayer_matrix, bayer_order)
+        }
+        DitherMethod::FloydSteinberg => {
+            dither_color_error_diffusion(width, height, rgb, palette, FLOYD_STEINBERG)
+        }
+        DitherMethod::Atkinson => {
+            dither_color_error_diffusion(width, height, rgb, palette, ATKINSON)
+        }
+        DitherMethod::Jarvis => dither_color_error_diffusion(width, height, rgb, palette, JARVIS),
+    }
+}
+
+fn dither_color_bayer(
+    width: u32,
+    height: u32,
+    rgb: &[[f32; 3]],
+    palette: &[[u8; 3]],
+    matrix: &[Vec<u32>],
+    order: usize,
+) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    let mut out = ImageBuffer::new(width, height);
+    // Map the matrix's 1..=order*order values onto a bias of up to +/-16 per channel.
+    let bias_scale = 32.0 / (order * order) as f32;
+
+    for y in 0..height {
+        for x in 0..width {
+            let color = rgb[(y * width + x) as usize];
+            let bias = matrix[y as usize % order][x as usize % order] as f32 * bias_scale - 16.0;
+            let biased = [
+                (color[0] + bias).clamp(0.0, 255.0),
+                (color[1] + bias).clamp(0.0, 255.0),
+                (color[2] + bias).clamp(0.0, 255.0),
+            ];
+            let nearest = palette[palette::nearest_index(palette, biased)];
+            out.put_pixel(x, y, Rgb(nearest));
+        }
+    }
+
+    out
+}
+
+fn dither_color_error_diffusion(
+    width: u32,
+    height: u32,
+    mut rgb: Vec<[f32; 3]>,
+    palette: &[[u8; 3]],
+    taps: &[ErrorTap],
+) -> ImageBuffer<Rgb<u8>, Vec<u8>> {
+    let w = width as i32;
+    let h = height as i32;
+    let mut out = ImageBuffer::new(width, height);
+
+    for y in 0..h {
+        for x in 0..w {
+            let idx = (y * w + x) as usize;
+            let old = rgb[idx];
+            let new = palette[palette::nearest_index(palette, old)];
+            let error = [
+                old[0] - new[0] as f32,
+                old[1] - new[1] as f32,
+                old[2] - new[2] as f32,
+            ];
+
+            out.put_pixel(x as u32, y as u32, Rgb(new));
+
+            for t in taps {
+                let nx = x + t.dx;
+                let ny = y + t.dy;
+                if nx >= 0 && nx < w && ny >= 0 && ny < h {
+                    let nidx = (ny * w + nx) as usize;
+                    for c in 0..3 {
+                        rgb[nidx][c] += error[c] * t.weight;
+                    }
+                }
+            }
+        }
+    }
+
+    out
+}
+
+fn dither_bayer(
+    width: u32,
+    height: u32,
+    gray: &[f32],
+    matrix: &[Vec<u32>],
+    order: usize,
+    scale_factor: u32,
+) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+    let mut out = ImageBuffer::new(width, height);
+
+    for y in 0..height {
+        for x in 0..width {
+            let gray_val = gray[(y * width + x) as usize] as u32;
+            let threshold = matrix[y as usize % order][x as usize % order] * scale_factor;
+            let new_pixel = if gray_val > threshold { 255 } else { 0 };
+            out.put_pixel(x, y, Luma([new_pixel as u8]));
+        }
+    }
+
+    out
+}
+
+/// Quantize `gray` to black/white in raster order, diffusing each pixel's
+/// error to its not-yet-visited neighbors per `taps`. `gray` is kept as a
+/// mutable `f32` working buffer so accumulated error can exceed 0..=255;
+/// the result is only clamped when written into the final `Luma<u8>`.
+fn dither_error_diffusion(
+    width: u32,
+    height: u32,
+    mut gray: Vec<f32>,
+    taps: &[ErrorTap],
+) -> ImageBuffer<Luma<u8>, Vec<u8>> {
+    let w = width as i32;
+    let h = height as i32;
+    let mut out = ImageBuffer::new(width, height);
+
+    for y in 0..h {
+        for x in 0..w {
+            let idx = (y * w + x) as usize;
+            let old = gray[idx];
+            let new = if old > 127.5 { 255.0 } else { 0.0 };
+            let error = old - new;
+
+            out.put_pixel(x as u32, y as u32, Luma([new.clamp(0.0, 255.0) as u8]));
+
+            for t in taps {
+                let nx = x + t.dx;
+                let ny = y + t.dy;
+                if nx >= 0 && nx < w && ny >= 0 && ny < h {
+                    gray[(ny * w + nx) as usize] += error * t.weight;
+                }
+            }
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn weight_sum(taps: &[ErrorTap]) -> f32 {
+        taps.iter().map(|t| t.weight).sum()
+    }
+
+    #[test]
+    fn floyd_steinberg_weights_sum_to_one() {
+        assert!((weight_sum(FLOYD_STEINBERG) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn jarvis_weights_sum_to_one() {
+        assert!((weight_sum(JARVIS) - 1.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn atkinson_weights_sum_to_three_quarters() {
+        // Atkinson intentionally discards 2/8 of the quantization error
+        // rather than diffusing all of it.
+        assert!((weight_sum(ATKINSON) - 0.75).abs() < 1e-6);
+    }
+
+    #[test]
+    fn dither_method_parse_round_trips_name() {
+        for method in [
+            DitherMethod::Bayer,
+            DitherMethod::FloydSteinberg,
+            DitherMethod::Atkinson,
+            DitherMethod::Jarvis,
+        ] {
+            assert_eq!(DitherMethod::parse(method.name()), Some(method));
+        }
+    }
+}
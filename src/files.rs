@@ -0,0 +1,54 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+pub const ALLOWED_EXTENSIONS: [&str; 7] = ["jpg", "jpeg", "png", "gif", "webp", "tiff", "bmp"];
+
+#[cfg(feature = "heif")]
+pub const HEIF_EXTENSIONS: [&str; 3] = ["heic", "heif", "avif"];
+
+#[cfg(feature = "raw")]
+pub const RAW_EXTENSIONS: [&str; 5] = ["cr2", "nef", "arw", "dng", "raf"];
+
+fn is_allowed(ext: &str) -> bool {
+    if ALLOWED_EXTENSIONS.contains(&ext) {
+        return true;
+    }
+
+    #[cfg(feature = "heif")]
+    if HEIF_EXTENSIONS.contains(&ext) {
+        return true;
+    }
+
+    #[cfg(feature = "raw")]
+    if RAW_EXTENSIONS.contains(&ext) {
+        return true;
+    }
+
+    false
+}
+
+/// Recursively list every file under `dir_path` with an allowed image
+/// extension (including HEIF/RAW extensions when those features are on).
+///
+/// `dithers/` output directories (see [`crate::cache::output_path`]) are
+/// excluded from the walk, so re-running over the same directory doesn't
+/// rediscover a previous run's outputs as new source images.
+pub fn list_image_files(dir_path: &Path) -> Result<Vec<PathBuf>> {
+    let files: Vec<PathBuf> = WalkDir::new(dir_path)
+        .into_iter()
+        .filter_entry(|e| e.file_name() != "dithers")
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .filter(|e| {
+            e.path()
+                .extension()
+                .and_then(std::ffi::OsStr::to_str)
+                .map(|ext| is_allowed(&ext.to_lowercase()))
+                .unwrap_or(false)
+        })
+        .map(|e| e.into_path())
+        .collect();
+
+    Ok(files)
+}
@@ -0,0 +1,127 @@
+use anyhow::{Context, Result};
+use image::codecs::jpeg::JpegEncoder;
+use image::{DynamicImage, ImageFormat};
+use std::fs::File;
+use std::path::Path;
+
+/// Output image format, chosen independently of the input's extension.
+///
+/// `WebP` has no quality knob: the `image` crate's WebP encoder is
+/// lossless-only, so unlike `Jpeg` there's nothing to tune.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OutputFormat {
+    Png,
+    Jpeg { quality: u8 },
+    WebP,
+    Bmp,
+    Gif,
+}
+
+impl OutputFormat {
+    pub fn parse(s: &str) -> Option<Self> {
+        let (kind, rest) = s.split_once(':').unwrap_or((s, ""));
+        match kind.to_lowercase().as_str() {
+            "png" => Some(OutputFormat::Png),
+            "jpeg" | "jpg" => Some(OutputFormat::Jpeg {
+                quality: rest.parse().unwrap_or(85),
+            }),
+            // WebP is lossless-only here, so a quality suffix is rejected
+            // rather than silently ignored.
+            "webp" if rest.is_empty() => Some(OutputFormat::WebP),
+            "bmp" => Some(OutputFormat::Bmp),
+            "gif" => Some(OutputFormat::Gif),
+            _ => None,
+        }
+    }
+
+    /// The [`OutputFormat`] matching a file extension (no leading dot),
+    /// used by `--keep-format` to pick an encoder mirroring the source file.
+    pub fn from_extension(ext: &str) -> Option<Self> {
+        match ext.to_lowercase().as_str() {
+            "png" => Some(OutputFormat::Png),
+            "jpeg" | "jpg" => Some(OutputFormat::Jpeg { quality: 85 }),
+            "webp" => Some(OutputFormat::WebP),
+            "bmp" => Some(OutputFormat::Bmp),
+            "gif" => Some(OutputFormat::Gif),
+            _ => None,
+        }
+    }
+
+    pub fn extension(&self) -> &'static str {
+        match self {
+            OutputFormat::Png => "png",
+            OutputFormat::Jpeg { .. } => "jpg",
+            OutputFormat::WebP => "webp",
+            OutputFormat::Bmp => "bmp",
+            OutputFormat::Gif => "gif",
+        }
+    }
+}
+
+/// Save `img` to `path` using `format`'s encoder.
+pub fn save(img: &DynamicImage, path: &Path, format: OutputFormat) -> Result<()> {
+    match format {
+        OutputFormat::Jpeg { quality } => {
+            let mut file = File::create(path)
+                .with_context(|| format!("Failed to create file: {}", path.display()))?;
+            let encoder = JpegEncoder::new_with_quality(&mut file, quality);
+            img.write_with_encoder(encoder)
+                .with_context(|| format!("Failed to save image: {}", path.display()))?;
+        }
+        OutputFormat::Png => {
+            img.save_with_format(path, ImageFormat::Png)
+                .with_context(|| format!("Failed to save image: {}", path.display()))?;
+        }
+        OutputFormat::WebP => {
+            img.save_with_format(path, ImageFormat::WebP)
+                .with_context(|| format!("Failed to save image: {}", path.display()))?;
+        }
+        OutputFormat::Bmp => {
+            img.save_with_format(path, ImageFormat::Bmp)
+                .with_context(|| format!("Failed to save image: {}", path.display()))?;
+        }
+        OutputFormat::Gif => {
+            img.save_with_format(path, ImageFormat::Gif)
+                .with_context(|| format!("Failed to save image: {}", path.display()))?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_accepts_known_formats() {
+        assert_eq!(OutputFormat::parse("png"), Some(OutputFormat::Png));
+        assert_eq!(OutputFormat::parse("jpeg"), Some(OutputFormat::Jpeg { quality: 85 }));
+        assert_eq!(OutputFormat::parse("jpeg:50"), Some(OutputFormat::Jpeg { quality: 50 }));
+        assert_eq!(OutputFormat::parse("jpg:50"), Some(OutputFormat::Jpeg { quality: 50 }));
+        assert_eq!(OutputFormat::parse("webp"), Some(OutputFormat::WebP));
+        assert_eq!(OutputFormat::parse("bmp"), Some(OutputFormat::Bmp));
+        assert_eq!(OutputFormat::parse("gif"), Some(OutputFormat::Gif));
+    }
+
+    #[test]
+    fn parse_rejects_webp_quality_suffix() {
+        assert_eq!(OutputFormat::parse("webp:75"), None);
+    }
+
+    #[test]
+    fn parse_rejects_garbage() {
+        assert_eq!(OutputFormat::parse("tiff"), None);
+        assert_eq!(OutputFormat::parse(""), None);
+    }
+
+    #[test]
+    fn from_extension_matches_parse_for_plain_names() {
+        assert_eq!(OutputFormat::from_extension("png"), Some(OutputFormat::Png));
+        assert_eq!(OutputFormat::from_extension("jpg"), Some(OutputFormat::Jpeg { quality: 85 }));
+        assert_eq!(OutputFormat::from_extension("webp"), Some(OutputFormat::WebP));
+        assert_eq!(OutputFormat::from_extension("bmp"), Some(OutputFormat::Bmp));
+        assert_eq!(OutputFormat::from_extension("gif"), Some(OutputFormat::Gif));
+        assert_eq!(OutputFormat::from_extension("tiff"), None);
+    }
+}
@@ -1,153 +1,291 @@
+mod cache;
+mod cli;
+mod decode;
+mod dither;
+mod files;
+mod format;
+mod palette;
+mod resize;
+mod stats;
+
 use anyhow::{Context, Result};
-use image::{imageops, DynamicImage, GenericImageView, ImageBuffer, ImageFormat, Luma};
+use clap::Parser;
+use cli::{Cli, Command, DitherArgs, Mode, StatsArgs};
+use dither::DitherMethod;
+use files::list_image_files;
+use format::OutputFormat;
+use image::DynamicImage;
+use indicatif::{ProgressBar, ProgressStyle};
 use log::{error, info, warn};
 use rayon::prelude::*;
-use std::env;
-use std::fs::{self};
+use resize::ResizeMode;
+use std::fs;
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
-
-const DEFAULT_MAX_IMAGE_SIDE: u32 = 800;
-const DEFAULT_BAYER_ORDER: usize = 8;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Palette-based color output: either a generated median-cut palette of
+/// `color_count` colors, or a fixed set of caller-supplied colors.
+#[derive(Debug, Clone)]
+enum ColorMode {
+    MedianCut { color_count: u32 },
+    FixedPalette { colors: Vec<[u8; 3]> },
+}
 
 /// Configuration for the image processing
 #[derive(Debug)]
 struct Config {
-    dir_path: PathBuf,
-    max_image_side: u32,
+    path: PathBuf,
+    mode: Mode,
+    resize_mode: ResizeMode,
     bayer_order: usize,
+    dither_method: DitherMethod,
+    color_mode: Option<ColorMode>,
+    force: bool,
+    output_format: OutputFormat,
+    keep_format: bool,
 }
 
 impl Config {
-    fn from_args() -> Result<Self> {
-        let args: Vec<String> = env::args().collect();
-        if args.len() < 2 {
-            anyhow::bail!(
-                "Usage: {} <path-to-images> [max_image_side] [bayer_order]",
-                args.get(0).unwrap_or(&String::from("program"))
-            );
+    fn from_args(args: DitherArgs) -> Result<Self> {
+        if !args.path.exists() {
+            anyhow::bail!("Path does not exist: {}", args.path.display());
         }
 
-        let dir_path = PathBuf::from(&args[1]);
-        if !dir_path.exists() {
-            anyhow::bail!("Directory does not exist: {}", dir_path.display());
-        }
-
-        let max_image_side = args
-            .get(2)
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(DEFAULT_MAX_IMAGE_SIDE);
-
-        let bayer_order = args
-            .get(3)
-            .and_then(|s| s.parse().ok())
-            .unwrap_or(DEFAULT_BAYER_ORDER);
+        let resize_mode = match args.resize {
+            Some(spec) => ResizeMode::parse(&spec)?,
+            None => {
+                let max_side = args.size.map(|s| s.max_side()).unwrap_or(args.max_side);
+                ResizeMode::MaxSide(max_side)
+            }
+        };
 
-        if !bayer_order.is_power_of_two() {
+        if !args.bayer_order.is_power_of_two() {
             anyhow::bail!("Bayer order must be a power of 2");
         }
 
+        let dither_method = DitherMethod::parse(&args.dither_method)
+            .ok_or_else(|| anyhow::anyhow!("Unknown dither method: {}", args.dither_method))?;
+
+        let output_format = match args.format {
+            Some(spec) => OutputFormat::parse(&spec)
+                .ok_or_else(|| anyhow::anyhow!("Unknown output format: {spec}"))?,
+            None => OutputFormat::Png,
+        };
+
+        let color_mode = if let Some(hex_list) = args.palette {
+            let hexes: Vec<String> = hex_list.split(',').map(str::to_string).collect();
+            Some(ColorMode::FixedPalette {
+                colors: palette::parse_hex_palette(&hexes)?,
+            })
+        } else if let Some(color_count) = args.colors {
+            if !color_count.is_power_of_two() {
+                anyhow::bail!("--colors must be a power of 2");
+            }
+            Some(ColorMode::MedianCut { color_count })
+        } else {
+            None
+        };
+
         Ok(Config {
-            dir_path,
-            max_image_side,
-            bayer_order,
+            path: args.path,
+            mode: args.mode,
+            resize_mode,
+            bayer_order: args.bayer_order,
+            dither_method,
+            color_mode,
+            force: args.force,
+            output_format,
+            keep_format: args.keep_format,
         })
     }
 }
 
 fn main() -> Result<()> {
     env_logger::init();
-    let config = Config::from_args()?;
 
-    info!(
-        "Starting image processing in directory: {}",
-        config.dir_path.display()
-    );
+    match Cli::parse().command {
+        Command::Dither(args) => run_dither(args),
+        Command::Stats(args) => run_stats(args),
+    }
+}
+
+fn run_dither(args: DitherArgs) -> Result<()> {
+    let threads = args.threads;
+    let config = Config::from_args(args)?;
+
+    let files = match config.mode {
+        Mode::Single => vec![config.path.clone()],
+        Mode::All => {
+            info!(
+                "Starting image processing in directory: {} (dither: {})",
+                config.path.display(),
+                config.dither_method.name()
+            );
+            list_image_files(&config.path)?
+        }
+    };
 
-    let files = list_image_files(&config.dir_path)?;
     if files.is_empty() {
         warn!("No image files found in the specified directory");
         return Ok(());
     }
 
-    // Process images in parallel
-    files.par_iter().for_each(|file| {
-        match process_image(file, config.max_image_side, config.bayer_order) {
-            Ok(_) => info!("âœ… Successfully processed: {}", file.display()),
-            Err(e) => error!("âŒ Failed to process {}: {}", file.display(), e),
-        }
-    });
+    let progress = ProgressBar::new(files.len() as u64);
+    progress.set_style(
+        ProgressStyle::with_template("{bar:40.cyan/blue} {pos}/{len} {msg}")
+            .unwrap_or_else(|_| ProgressStyle::default_bar()),
+    );
+
+    let succeeded = AtomicUsize::new(0);
+    let failed = AtomicUsize::new(0);
+
+    let process_all = || {
+        files.par_iter().for_each(|file| {
+            match process_image(file, &config) {
+                Ok(_) => {
+                    succeeded.fetch_add(1, Ordering::Relaxed);
+                    info!("âœ… Successfully processed: {}", file.display());
+                }
+                Err(e) => {
+                    failed.fetch_add(1, Ordering::Relaxed);
+                    error!("âŒ Failed to process {}: {}", file.display(), e);
+                }
+            }
+            progress.set_message(format!(
+                "{} ok, {} failed",
+                succeeded.load(Ordering::Relaxed),
+                failed.load(Ordering::Relaxed)
+            ));
+            progress.inc(1);
+        });
+    };
+
+    match threads {
+        Some(n) => rayon::ThreadPoolBuilder::new()
+            .num_threads(n)
+            .build()?
+            .install(process_all),
+        None => process_all(),
+    }
+
+    progress.finish_and_clear();
+    info!(
+        "Processed {} images ({} failed)",
+        succeeded.load(Ordering::Relaxed),
+        failed.load(Ordering::Relaxed)
+    );
 
     Ok(())
 }
 
-fn list_image_files(dir_path: &Path) -> Result<Vec<PathBuf>> {
-    const ALLOWED_EXTENSIONS: [&str; 7] = ["jpg", "jpeg", "png", "gif", "webp", "tiff", "bmp"];
-
-    let files: Vec<PathBuf> = WalkDir::new(dir_path)
-        .into_iter()
-        .filter_map(|e| e.ok())
-        .filter(|e| e.file_type().is_file())
-        .filter(|e| {
-            e.path()
-                .extension()
-                .and_then(std::ffi::OsStr::to_str)
-                .map(|ext| ALLOWED_EXTENSIONS.contains(&ext.to_lowercase().as_str()))
-                .unwrap_or(false)
-        })
-        .map(|e| e.into_path())
-        .collect();
-
-    Ok(files)
+fn run_stats(args: StatsArgs) -> Result<()> {
+    let report = stats::collect(&args.path)?;
+    stats::print_report(&report);
+    Ok(())
 }
 
-fn process_image(file: &Path, max_image_side: u32, bayer_order: usize) -> Result<()> {
-    let img =
-        image::open(file).with_context(|| format!("Failed to open image: {}", file.display()))?;
+fn process_image(file: &Path, config: &Config) -> Result<()> {
+    let bytes =
+        fs::read(file).with_context(|| format!("Failed to read image: {}", file.display()))?;
+
+    // With --keep-format, encode with whatever format matches the source
+    // extension (falling back to --format for extensions we don't encode,
+    // e.g. tiff) instead of always using config.output_format.
+    let output_format = if config.keep_format {
+        file.extension()
+            .and_then(|s| s.to_str())
+            .and_then(OutputFormat::from_extension)
+            .unwrap_or(config.output_format)
+    } else {
+        config.output_format
+    };
+
+    let params = format!(
+        "{:?}|{}|{:?}|{:?}|{:?}",
+        config.resize_mode, config.bayer_order, config.dither_method, config.color_mode, output_format
+    );
+    let key = cache::content_key(&bytes, &params);
+
+    let output_path = cache::output_path(file, &key, output_format.extension())?;
 
-    let dithered_img = apply_bayer_dithering_and_resize(img, bayer_order, max_image_side);
-    save_image(&dithered_img, file)?;
+    if !config.force && output_path.exists() {
+        info!("Skipping {} (cached at {})", file.display(), output_path.display());
+        return Ok(());
+    }
+
+    let img = decode::open(&bytes, file)?;
+
+    let dithered_img = dither_and_resize(img, config);
+    save_image(&dithered_img, &output_path, output_format)?;
 
     Ok(())
 }
 
-fn apply_bayer_dithering_and_resize(
-    img: DynamicImage,
-    order: usize,
-    max_image_side: u32,
-) -> DynamicImage {
-    // Resize logic
-    let (width, height) = img.dimensions();
-    let max_side = width.max(height);
-    let scale = if max_side > max_image_side {
-        max_image_side as f64 / max_side as f64
-    } else {
-        1.0
-    };
+fn dither_and_resize(img: DynamicImage, config: &Config) -> DynamicImage {
+    let order = config.bayer_order;
 
-    let new_width = (width as f64 * scale).round() as u32;
-    let new_height = (height as f64 * scale).round() as u32;
-    let resized_img = imageops::resize(&img, new_width, new_height, imageops::FilterType::Lanczos3);
+    let resized_img = resize::resize(&img, config.resize_mode);
+    let (new_width, new_height) = resized_img.dimensions();
 
-    // Convert to grayscale and apply dithering
     let bayer_matrix = generate_bayer_matrix(order);
+
+    if let Some(color_mode) = &config.color_mode {
+        let mut rgb = Vec::with_capacity((new_width * new_height) as usize);
+        for y in 0..new_height {
+            for x in 0..new_width {
+                let pixel = resized_img.get_pixel(x, y);
+                rgb.push([pixel[0] as f32, pixel[1] as f32, pixel[2] as f32]);
+            }
+        }
+
+        let color_palette = match color_mode {
+            ColorMode::FixedPalette { colors } => colors.clone(),
+            ColorMode::MedianCut { color_count } => {
+                let samples: Vec<[u8; 3]> = rgb
+                    .iter()
+                    .map(|p| [p[0] as u8, p[1] as u8, p[2] as u8])
+                    .collect();
+                palette::median_cut(&samples, *color_count)
+            }
+        };
+
+        let buffer = dither::dither_color(
+            new_width,
+            new_height,
+            rgb,
+            &color_palette,
+            config.dither_method,
+            &bayer_matrix,
+            order,
+        );
+
+        return DynamicImage::ImageRgb8(buffer);
+    }
+
+    // Convert to a grayscale working buffer, then dither it
     let max_value = (order * order) as u32;
     let scale_factor = 256 / max_value;
 
-    let mut buffer: ImageBuffer<Luma<u8>, Vec<u8>> = ImageBuffer::new(new_width, new_height);
-
+    let mut gray = Vec::with_capacity((new_width * new_height) as usize);
     for y in 0..new_height {
         for x in 0..new_width {
             let pixel = resized_img.get_pixel(x, y);
-            let gray = ((pixel[0] as f32 * 0.299)
-                + (pixel[1] as f32 * 0.587)
-                + (pixel[2] as f32 * 0.114)) as u32;
-            let threshold = bayer_matrix[y as usize % order][x as usize % order] * scale_factor;
-            let new_pixel = if gray > threshold { 255 } else { 0 };
-            buffer.put_pixel(x, y, Luma([new_pixel as u8]));
+            gray.push(
+                (pixel[0] as f32 * 0.299) + (pixel[1] as f32 * 0.587) + (pixel[2] as f32 * 0.114),
+            );
         }
     }
 
+    let buffer = dither::dither(
+        new_width,
+        new_height,
+        gray,
+        config.dither_method,
+        &bayer_matrix,
+        order,
+        scale_factor,
+    );
+
     DynamicImage::ImageLuma8(buffer)
 }
 
@@ -182,24 +320,9 @@ fn generate_bayer_matrix(order: usize) -> Vec<Vec<u32>> {
     matrix
 }
 
-fn save_image(img: &DynamicImage, original_path: &Path) -> Result<()> {
-    let dither_dir = original_path
-        .parent()
-        .unwrap_or_else(|| Path::new(""))
-        .join("dithers");
-
-    fs::create_dir_all(&dither_dir)
-        .with_context(|| format!("Failed to create directory: {}", dither_dir.display()))?;
-
-    let new_path = dither_dir.join(
-        original_path
-            .file_name()
-            .ok_or_else(|| anyhow::anyhow!("Invalid file name"))?,
-    );
-
-    img.save_with_format(&new_path, ImageFormat::Png)
-        .with_context(|| format!("Failed to save image: {}", new_path.display()))?;
+fn save_image(img: &DynamicImage, output_path: &Path, output_format: OutputFormat) -> Result<()> {
+    format::save(img, output_path, output_format)?;
 
-    info!("ðŸ–¼ Image saved to {}", new_path.display());
+    info!("ðŸ–¼ Image saved to {}", output_path.display());
     Ok(())
 }
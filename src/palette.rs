@@ -0,0 +1,167 @@
+/// Build a palette of `color_count` colors from `pixels` using median-cut
+/// quantization: start with one box spanning every pixel, repeatedly split
+/// the box with the largest channel range at its median along that channel,
+/// until there are `color_count` boxes (or every box holds a single pixel),
+/// then average each box.
+///
+/// `color_count` must be a power of two.
+pub fn median_cut(pixels: &[[u8; 3]], color_count: u32) -> Vec<[u8; 3]> {
+    debug_assert!(color_count.is_power_of_two());
+
+    if pixels.is_empty() {
+        return vec![[0, 0, 0]; color_count as usize];
+    }
+
+    let mut boxes = vec![pixels.to_vec()];
+
+    while boxes.len() < color_count as usize {
+        let widest = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, b)| b.len() > 1)
+            .max_by_key(|(_, b)| (0..3).map(|c| channel_range(b, c)).max().unwrap())
+            .map(|(i, _)| i);
+        let Some(i) = widest else { break };
+        let b = boxes.swap_remove(i);
+        boxes.extend(split_box(b));
+    }
+
+    boxes.iter().map(|b| average_color(b)).collect()
+}
+
+/// Parse a fixed palette from `#rrggbb`/`rrggbb` hex strings.
+pub fn parse_hex_palette(hexes: &[String]) -> anyhow::Result<Vec<[u8; 3]>> {
+    hexes
+        .iter()
+        .map(|hex| {
+            let hex = hex.trim_start_matches('#');
+            if hex.len() != 6 {
+                anyhow::bail!("Invalid palette color: {hex}");
+            }
+            let r = u8::from_str_radix(&hex[0..2], 16)?;
+            let g = u8::from_str_radix(&hex[2..4], 16)?;
+            let b = u8::from_str_radix(&hex[4..6], 16)?;
+            Ok([r, g, b])
+        })
+        .collect()
+}
+
+/// Index of the palette entry nearest `color` by squared Euclidean distance.
+pub fn nearest_index(palette: &[[u8; 3]], color: [f32; 3]) -> usize {
+    palette
+        .iter()
+        .enumerate()
+        .min_by(|(_, a), (_, b)| {
+            squared_distance(color, **a)
+                .partial_cmp(&squared_distance(color, **b))
+                .unwrap()
+        })
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+fn squared_distance(color: [f32; 3], palette_color: [u8; 3]) -> f32 {
+    (0..3)
+        .map(|c| {
+            let d = color[c] - palette_color[c] as f32;
+            d * d
+        })
+        .sum()
+}
+
+fn channel_range(box_pixels: &[[u8; 3]], channel: usize) -> u8 {
+    let mut min = u8::MAX;
+    let mut max = u8::MIN;
+    for p in box_pixels {
+        min = min.min(p[channel]);
+        max = max.max(p[channel]);
+    }
+    max - min
+}
+
+fn split_box(mut box_pixels: Vec<[u8; 3]>) -> Vec<Vec<[u8; 3]>> {
+    if box_pixels.len() <= 1 {
+        return vec![box_pixels];
+    }
+
+    let widest_channel = (0..3)
+        .max_by_key(|&c| channel_range(&box_pixels, c))
+        .unwrap();
+
+    box_pixels.sort_by_key(|p| p[widest_channel]);
+    let median = box_pixels.len() / 2;
+    let upper = box_pixels.split_off(median);
+    vec![box_pixels, upper]
+}
+
+fn average_color(box_pixels: &[[u8; 3]]) -> [u8; 3] {
+    let mut sum = [0u64; 3];
+    for p in box_pixels {
+        for (c, s) in sum.iter_mut().enumerate() {
+            *s += p[c] as u64;
+        }
+    }
+    let n = box_pixels.len().max(1) as u64;
+    [
+        (sum[0] / n) as u8,
+        (sum[1] / n) as u8,
+        (sum[2] / n) as u8,
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn median_cut_returns_requested_color_count() {
+        let pixels = vec![[0, 0, 0], [10, 10, 10], [250, 250, 250], [240, 240, 240]];
+        let result = median_cut(&pixels, 2);
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn median_cut_separates_distinct_clusters() {
+        // Two well-separated clusters: a dark one near black, a bright one
+        // near white. Two colors should land near each cluster's average.
+        let pixels = vec![
+            [0, 0, 0],
+            [4, 4, 4],
+            [8, 0, 8],
+            [252, 252, 252],
+            [248, 248, 248],
+            [255, 244, 255],
+        ];
+        let result = median_cut(&pixels, 2);
+
+        assert_eq!(result.len(), 2);
+        let dark = result.iter().find(|c| c[0] < 128).expect("a dark color");
+        let bright = result.iter().find(|c| c[0] >= 128).expect("a bright color");
+        assert!(dark[0] < 20 && dark[1] < 20 && dark[2] < 20);
+        assert!(bright[0] > 235 && bright[1] > 235 && bright[2] > 235);
+    }
+
+    #[test]
+    fn median_cut_handles_empty_input() {
+        let result = median_cut(&[], 4);
+        assert_eq!(result, vec![[0, 0, 0]; 4]);
+    }
+
+    #[test]
+    fn nearest_index_picks_closest_color() {
+        let palette = vec![[0, 0, 0], [255, 255, 255]];
+        assert_eq!(nearest_index(&palette, [10.0, 10.0, 10.0]), 0);
+        assert_eq!(nearest_index(&palette, [240.0, 240.0, 240.0]), 1);
+    }
+
+    #[test]
+    fn parse_hex_palette_accepts_with_and_without_hash() {
+        let colors = parse_hex_palette(&["#ff0000".to_string(), "00ff00".to_string()]).unwrap();
+        assert_eq!(colors, vec![[255, 0, 0], [0, 255, 0]]);
+    }
+
+    #[test]
+    fn parse_hex_palette_rejects_invalid_length() {
+        assert!(parse_hex_palette(&["fff".to_string()]).is_err());
+    }
+}
@@ -0,0 +1,175 @@
+use image::{imageops, DynamicImage, GenericImageView, Rgba};
+
+/// How an image should be resized before dithering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResizeMode {
+    /// Scale the longest side down to `max_side`, preserving aspect ratio.
+    /// This is the original/default behavior.
+    MaxSide(u32),
+    /// Scale to exactly `width`x`height`, ignoring aspect ratio.
+    Scale(u32, u32),
+    /// Scale so the width is exactly `width`, preserving aspect ratio.
+    FitWidth(u32),
+    /// Scale so the height is exactly `height`, preserving aspect ratio.
+    FitHeight(u32),
+    /// Scale to the largest size that fits inside `width`x`height`,
+    /// preserving aspect ratio.
+    Fit(u32, u32),
+    /// Scale to cover `width`x`height`, preserving aspect ratio, then
+    /// center-crop the overflow to exactly `width`x`height`.
+    Fill(u32, u32),
+}
+
+impl ResizeMode {
+    /// Parse a `<mode>=<spec>` string, e.g. `scale=200x100`, `fit-width=200`,
+    /// `fit=200x100`, `fill=200x100`.
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let (kind, rest) = spec
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid --resize spec: {spec}"))?;
+
+        match kind {
+            "scale" => {
+                let (w, h) = parse_dims(rest)?;
+                Ok(ResizeMode::Scale(w, h))
+            }
+            "fit-width" => Ok(ResizeMode::FitWidth(rest.parse()?)),
+            "fit-height" => Ok(ResizeMode::FitHeight(rest.parse()?)),
+            "fit" => {
+                let (w, h) = parse_dims(rest)?;
+                Ok(ResizeMode::Fit(w, h))
+            }
+            "fill" => {
+                let (w, h) = parse_dims(rest)?;
+                Ok(ResizeMode::Fill(w, h))
+            }
+            other => anyhow::bail!("Unknown resize mode: {other}"),
+        }
+    }
+}
+
+fn parse_dims(s: &str) -> anyhow::Result<(u32, u32)> {
+    let (w, h) = s
+        .split_once('x')
+        .ok_or_else(|| anyhow::anyhow!("Expected WxH, got {s}"))?;
+    Ok((w.parse()?, h.parse()?))
+}
+
+/// Resize `img` according to `mode`.
+pub fn resize(img: &DynamicImage, mode: ResizeMode) -> image::ImageBuffer<Rgba<u8>, Vec<u8>> {
+    let (width, height) = img.dimensions();
+
+    match mode {
+        ResizeMode::MaxSide(max_side) => {
+            let cur_max = width.max(height);
+            let scale = if cur_max > max_side {
+                max_side as f64 / cur_max as f64
+            } else {
+                1.0
+            };
+            let (w, h) = scaled_dims(width, height, scale);
+            imageops::resize(img, w, h, imageops::FilterType::Lanczos3)
+        }
+        ResizeMode::Scale(w, h) => imageops::resize(img, w, h, imageops::FilterType::Lanczos3),
+        ResizeMode::FitWidth(w) => {
+            let scale = w as f64 / width as f64;
+            let (w, h) = scaled_dims(width, height, scale);
+            imageops::resize(img, w, h, imageops::FilterType::Lanczos3)
+        }
+        ResizeMode::FitHeight(h) => {
+            let scale = h as f64 / height as f64;
+            let (w, h) = scaled_dims(width, height, scale);
+            imageops::resize(img, w, h, imageops::FilterType::Lanczos3)
+        }
+        ResizeMode::Fit(box_w, box_h) => {
+            let scale = (box_w as f64 / width as f64).min(box_h as f64 / height as f64);
+            let (w, h) = scaled_dims(width, height, scale);
+            imageops::resize(img, w, h, imageops::FilterType::Lanczos3)
+        }
+        ResizeMode::Fill(box_w, box_h) => {
+            let scale = (box_w as f64 / width as f64).max(box_h as f64 / height as f64);
+            let (w, h) = scaled_dims(width, height, scale);
+            let resized = imageops::resize(img, w, h, imageops::FilterType::Lanczos3);
+
+            let x = (w.saturating_sub(box_w)) / 2;
+            let y = (h.saturating_sub(box_h)) / 2;
+            imageops::crop_imm(&resized, x, y, box_w.min(w), box_h.min(h)).to_image()
+        }
+    }
+}
+
+fn scaled_dims(width: u32, height: u32, scale: f64) -> (u32, u32) {
+    (
+        (width as f64 * scale).round() as u32,
+        (height as f64 * scale).round() as u32,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::RgbImage;
+
+    fn test_image(width: u32, height: u32) -> DynamicImage {
+        DynamicImage::ImageRgb8(RgbImage::new(width, height))
+    }
+
+    #[test]
+    fn max_side_scales_down_the_longest_side() {
+        let out = resize(&test_image(400, 200), ResizeMode::MaxSide(100));
+        assert_eq!(out.dimensions(), (100, 50));
+    }
+
+    #[test]
+    fn max_side_leaves_smaller_images_untouched() {
+        let out = resize(&test_image(50, 50), ResizeMode::MaxSide(100));
+        assert_eq!(out.dimensions(), (50, 50));
+    }
+
+    #[test]
+    fn scale_ignores_aspect_ratio() {
+        let out = resize(&test_image(400, 200), ResizeMode::Scale(100, 100));
+        assert_eq!(out.dimensions(), (100, 100));
+    }
+
+    #[test]
+    fn fit_width_preserves_aspect_ratio() {
+        let out = resize(&test_image(400, 200), ResizeMode::FitWidth(200));
+        assert_eq!(out.dimensions(), (200, 100));
+    }
+
+    #[test]
+    fn fit_height_preserves_aspect_ratio() {
+        let out = resize(&test_image(400, 200), ResizeMode::FitHeight(50));
+        assert_eq!(out.dimensions(), (100, 50));
+    }
+
+    #[test]
+    fn fit_shrinks_to_the_binding_dimension() {
+        // 400x200 into a 100x100 box: width is the binding constraint.
+        let out = resize(&test_image(400, 200), ResizeMode::Fit(100, 100));
+        assert_eq!(out.dimensions(), (100, 50));
+    }
+
+    #[test]
+    fn fill_covers_then_crops_to_exact_size() {
+        let out = resize(&test_image(400, 200), ResizeMode::Fill(100, 100));
+        assert_eq!(out.dimensions(), (100, 100));
+    }
+
+    #[test]
+    fn parse_accepts_every_mode() {
+        assert_eq!(ResizeMode::parse("scale=200x100").unwrap(), ResizeMode::Scale(200, 100));
+        assert_eq!(ResizeMode::parse("fit-width=200").unwrap(), ResizeMode::FitWidth(200));
+        assert_eq!(ResizeMode::parse("fit-height=200").unwrap(), ResizeMode::FitHeight(200));
+        assert_eq!(ResizeMode::parse("fit=200x100").unwrap(), ResizeMode::Fit(200, 100));
+        assert_eq!(ResizeMode::parse("fill=200x100").unwrap(), ResizeMode::Fill(200, 100));
+    }
+
+    #[test]
+    fn parse_rejects_malformed_specs() {
+        assert!(ResizeMode::parse("200x100").is_err());
+        assert!(ResizeMode::parse("scale=200").is_err());
+        assert!(ResizeMode::parse("bogus=200x100").is_err());
+    }
+}
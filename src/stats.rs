@@ -0,0 +1,63 @@
+use crate::files::list_image_files;
+use anyhow::Result;
+use std::collections::HashMap;
+use std::path::Path;
+
+/// Dry-run summary of a directory's images, gathered without dithering or
+/// resizing anything.
+#[derive(Debug, Default)]
+pub struct Stats {
+    pub count: usize,
+    pub total_bytes: u64,
+    pub bytes_by_format: HashMap<String, u64>,
+    pub count_by_format: HashMap<String, usize>,
+    pub dimension_counts: HashMap<(u32, u32), usize>,
+}
+
+/// Walk `dir_path` and gather [`Stats`] without processing anything.
+pub fn collect(dir_path: &Path) -> Result<Stats> {
+    let files = list_image_files(dir_path)?;
+    let mut stats = Stats::default();
+
+    for file in &files {
+        let size = std::fs::metadata(file)?.len();
+        let ext = file
+            .extension()
+            .and_then(|s| s.to_str())
+            .unwrap_or("unknown")
+            .to_lowercase();
+
+        stats.count += 1;
+        stats.total_bytes += size;
+        *stats.bytes_by_format.entry(ext.clone()).or_insert(0) += size;
+        *stats.count_by_format.entry(ext).or_insert(0) += 1;
+
+        if let Ok(dimensions) = image::image_dimensions(file) {
+            *stats.dimension_counts.entry(dimensions).or_insert(0) += 1;
+        }
+    }
+
+    Ok(stats)
+}
+
+/// Print a human-readable report of `stats` to stdout.
+pub fn print_report(stats: &Stats) {
+    println!("Files: {}", stats.count);
+    println!("Total size: {} bytes", stats.total_bytes);
+
+    println!("By format:");
+    let mut formats: Vec<&String> = stats.count_by_format.keys().collect();
+    formats.sort();
+    for ext in formats {
+        let count = stats.count_by_format[ext];
+        let bytes = stats.bytes_by_format.get(ext).copied().unwrap_or(0);
+        println!("  {ext}: {count} files, {bytes} bytes");
+    }
+
+    println!("Dimensions:");
+    let mut dimensions: Vec<&(u32, u32)> = stats.dimension_counts.keys().collect();
+    dimensions.sort();
+    for (w, h) in dimensions {
+        println!("  {w}x{h}: {}", stats.dimension_counts[&(*w, *h)]);
+    }
+}